@@ -0,0 +1,160 @@
+//! Optional LLM-assisted deck generation and on-demand grammar explanations,
+//! gated behind the `llm-assistant` feature so the core app stays dependency-light.
+//! Request/response shape borrows the system+user message structure and token-budget
+//! trimming used by chat-completion assistants generally.
+
+use serde::{Deserialize, Serialize};
+
+use grammar::{Entry, GramClass};
+
+use crate::settings::Persisted;
+
+/// Rough budget for the user message, tiktoken-style (~4 characters per token).
+const MAX_PROMPT_TOKENS: usize = 2_000;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum AssistantError {
+    MissingConfig,
+    Http,
+    InvalidReply,
+}
+
+#[derive(Debug, Serialize)]
+struct ChatMessage {
+    role: &'static str,
+    content: String,
+}
+
+#[derive(Debug, Serialize)]
+struct ChatRequest {
+    model: &'static str,
+    messages: Vec<ChatMessage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatResponse {
+    choices: Vec<ChatChoice>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatChoice {
+    message: ChatChoiceMessage,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatChoiceMessage {
+    content: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawEntry {
+    lang0: String,
+    lang1: String,
+    gram_class: String,
+}
+
+fn parse_gram_class(class: &str) -> Option<GramClass> {
+    match class {
+        "Noun" => Some(GramClass::Noun),
+        "Verb" => Some(GramClass::Verb),
+        "Adverb" => Some(GramClass::Adverb),
+        _ => None,
+    }
+}
+
+fn estimate_tokens(text: &str) -> usize {
+    (text.chars().count() / 4).max(1)
+}
+
+/// Trim `text` from the end until it fits within `budget` tokens.
+fn truncate_to_budget(text: String, budget: usize) -> String {
+    if estimate_tokens(&text) <= budget {
+        return text;
+    }
+    let max_chars = budget.saturating_mul(4).max(1);
+    text.chars().take(max_chars).collect()
+}
+
+async fn complete(
+    settings: &Persisted,
+    system: &str,
+    user: &str,
+) -> Result<String, AssistantError> {
+    if settings.assistant_endpoint.is_empty() || settings.assistant_api_key.is_empty() {
+        return Err(AssistantError::MissingConfig);
+    }
+    let user = truncate_to_budget(user.to_string(), MAX_PROMPT_TOKENS);
+
+    let request = ChatRequest {
+        model: "gpt-4o-mini",
+        messages: vec![
+            ChatMessage {
+                role: "system",
+                content: system.to_string(),
+            },
+            ChatMessage {
+                role: "user",
+                content: user,
+            },
+        ],
+    };
+
+    let response = reqwest::Client::new()
+        .post(&settings.assistant_endpoint)
+        .bearer_auth(&settings.assistant_api_key)
+        .json(&request)
+        .send()
+        .await
+        .map_err(|_| AssistantError::Http)?
+        .json::<ChatResponse>()
+        .await
+        .map_err(|_| AssistantError::Http)?;
+
+    response
+        .choices
+        .into_iter()
+        .next()
+        .map(|choice| choice.message.content)
+        .ok_or(AssistantError::InvalidReply)
+}
+
+/// Ask the model for a fresh vocabulary deck about `topic`.
+pub(crate) async fn generate_deck(
+    settings: Persisted,
+    topic: String,
+) -> Result<Vec<Entry>, AssistantError> {
+    let system = "You generate language-learning flashcard decks. Reply with a JSON array of \
+        objects shaped as {\"lang0\": ..., \"lang1\": ..., \"gram_class\": \"Noun\"|\"Verb\"|\"Adverb\"}, \
+        JSON only, no prose.";
+    let user = format!("Generate 10 flashcards about: {topic}");
+
+    let raw = complete(&settings, system, &user).await?;
+    let entries: Vec<RawEntry> =
+        serde_json::from_str(&raw).map_err(|_| AssistantError::InvalidReply)?;
+
+    let entries: Vec<Entry> = entries
+        .into_iter()
+        .filter_map(|e| {
+            let class = parse_gram_class(&e.gram_class)?;
+            Some(Entry(e.lang0, e.lang1, class))
+        })
+        .collect();
+
+    if entries.is_empty() {
+        Err(AssistantError::InvalidReply)
+    } else {
+        Ok(entries)
+    }
+}
+
+/// Ask the model why `expected` is the right answer to `prompt`.
+pub(crate) async fn explain(
+    settings: Persisted,
+    prompt: String,
+    expected: String,
+) -> Result<String, AssistantError> {
+    let system = "You are a concise language tutor. Explain the grammar (agreement, gender, \
+        conjugation...) behind an answer in two sentences or fewer.";
+    let user = format!("Prompt: {prompt}\nExpected answer: {expected}\nWhy is this the answer?");
+    complete(&settings, system, &user).await
+}
@@ -0,0 +1,112 @@
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
+
+use crate::settings::GradingMode;
+
+/// Lowercase and strip diacritics so "élève" compares equal to "eleve".
+fn normalize(s: &str) -> String {
+    s.nfd()
+        .filter(|c| !is_combining_mark(*c))
+        .collect::<String>()
+        .to_lowercase()
+}
+
+/// Classic DP edit distance, one row at a time.
+fn levenshtein(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut curr = vec![0usize; b.len() + 1];
+
+    for i in 1..=a.len() {
+        curr[0] = i;
+        for j in 1..=b.len() {
+            let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            curr[j] = (prev[j] + 1).min(curr[j - 1] + 1).min(prev[j - 1] + cost);
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+    prev[b.len()]
+}
+
+/// Grade `entry` against `expected`, which may list several acceptable answers
+/// separated by `/` or `;`. Returns the best `(score, distance)` across all of them.
+pub fn grade(entry: &str, expected: &str, mode: GradingMode) -> (f32, usize) {
+    let entry = normalize(entry);
+
+    let scored: Vec<(f32, usize)> = expected
+        .split(['/', ';'])
+        .map(str::trim)
+        .filter(|candidate| !candidate.is_empty())
+        .map(normalize)
+        .map(|candidate| {
+            let distance = levenshtein(&entry, &candidate);
+            let score = if distance == 0 {
+                1.0
+            } else {
+                match mode {
+                    GradingMode::Strict => 0.0,
+                    GradingMode::Lenient => {
+                        (1.0 - distance as f32 / candidate.chars().count().max(1) as f32).max(0.0)
+                    }
+                }
+            };
+            (score, distance)
+        })
+        .collect();
+
+    // `expected` with no non-empty candidates (blank entry) isn't a match worth
+    // reporting a bogus distance for.
+    scored
+        .into_iter()
+        .fold(None, |best, cur| match best {
+            Some(best) if best.0 >= cur.0 => Some(best),
+            _ => Some(cur),
+        })
+        .unwrap_or((0.0, 0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exact_match_scores_one_with_zero_distance() {
+        assert_eq!(grade("oui", "oui", GradingMode::Strict), (1.0, 0));
+    }
+
+    #[test]
+    fn strict_mode_zeroes_any_miss() {
+        let (score, distance) = grade("oiu", "oui", GradingMode::Strict);
+        assert_eq!(score, 0.0);
+        assert_eq!(distance, 2);
+    }
+
+    #[test]
+    fn lenient_mode_scores_by_distance_ratio() {
+        let (score, distance) = grade("oiu", "oui", GradingMode::Lenient);
+        assert_eq!(distance, 2);
+        assert!((score - (1.0 - 2.0 / 3.0)).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn picks_best_of_slash_separated_candidates() {
+        assert_eq!(grade("non", "oui/non", GradingMode::Strict), (1.0, 0));
+    }
+
+    #[test]
+    fn picks_best_of_semicolon_separated_candidates() {
+        assert_eq!(grade("non", "oui; non", GradingMode::Strict), (1.0, 0));
+    }
+
+    #[test]
+    fn blank_expected_does_not_leak_the_fold_seed() {
+        assert_eq!(grade("oui", "", GradingMode::Strict), (0.0, 0));
+        assert_eq!(grade("oui", " / ;", GradingMode::Strict), (0.0, 0));
+    }
+
+    #[test]
+    fn diacritics_are_ignored() {
+        assert_eq!(grade("eleve", "élève", GradingMode::Strict), (1.0, 0));
+    }
+}
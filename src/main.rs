@@ -9,12 +9,24 @@ use iced::{
 use iced_aw::menu::{self, Item};
 use iced_aw::{grid, grid_row};
 use rand::{seq::SliceRandom, thread_rng};
-use std::{path::PathBuf, sync::Arc, vec};
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+    sync::Arc,
+    vec,
+};
 
 use grammar::*;
+#[cfg(feature = "llm-assistant")]
+mod assistant;
+mod grading;
 mod settings;
+mod srs;
+mod storage;
 mod style;
 
+use settings::GradingMode;
+
 fn main() -> iced::Result {
     App::run(iced::Settings {
         window: iced::window::Settings {
@@ -33,35 +45,83 @@ struct App {
     current: Option<usize>,
     entry: String,
     error: Option<Error>,
-    file: Option<PathBuf>,
+    file: Option<DeckSource>,
     langs: [Lang; 2],
     state: State,
     last_score: f32,
-    dark_theme: bool,
+    last_distance: usize,
+    theme_mode: settings::ThemeMode,
+    system_dark: bool,
     total_score: (f32, usize),
     font_size: Pixels,
     spacing: f32,
+    grading_mode: GradingMode,
+    schedule: srs::Deck,
+    #[cfg(feature = "llm-assistant")]
+    assistant_endpoint: String,
+    #[cfg(feature = "llm-assistant")]
+    assistant_api_key: String,
+    #[cfg(feature = "llm-assistant")]
+    topic_input: String,
+    #[cfg(feature = "llm-assistant")]
+    explanation: Option<String>,
 }
 
 impl App {
     fn init(&mut self, mut content: Vec<Entry>) {
         self.entry = String::new();
-        self.current = Some(0);
         content.shuffle(&mut thread_rng());
+        let today = chrono::Utc::now().date_naive();
+        content.sort_by_key(|entry| {
+            let due = self.schedule.due_date(&srs::Deck::key(entry));
+            (due > today, due)
+        });
+        self.current = Some(0);
         self.content = content;
         self.total_score = (0., self.content.len());
         self.last_score = 0.;
+        self.last_distance = 0;
         self.state = State::WaitUserAnswer;
     }
     fn correct(&mut self) {
-        self.last_score = self.content[self.current.unwrap()].correct(
-            &self.entry.trim().into(),
-            0,
-            &self.langs[0],
-        );
+        let idx = self.current.unwrap();
+        let expected = self.content[idx].get(0).to_string();
+        let (score, distance) = grading::grade(self.entry.trim(), &expected, self.grading_mode);
+        self.last_score = score;
+        self.last_distance = distance;
         self.total_score.0 += self.last_score;
+
+        let today = chrono::Utc::now().date_naive();
+        let key = srs::Deck::key(&self.content[idx]);
+        let quality = srs::Schedule::quality(score);
+        self.schedule.entry(&key).review(quality, today);
+        if quality < 3 {
+            let resurface_at = (idx + 1 + 3).min(self.content.len());
+            let entry = self.content[idx].clone();
+            self.content.insert(resurface_at, entry);
+            self.total_score.1 = self.content.len();
+        }
+        if let Some(path) = self.file.as_ref().and_then(DeckSource::as_path) {
+            self.schedule.save(path);
+        }
+
         self.state = State::Correcting;
     }
+    fn persisted(&self) -> settings::Persisted {
+        settings::Persisted {
+            theme_mode: self.theme_mode,
+            font_size: self.font_size.0,
+            spacing: self.spacing,
+            grading_mode: self.grading_mode,
+            #[cfg(feature = "llm-assistant")]
+            assistant_endpoint: self.assistant_endpoint.clone(),
+            #[cfg(feature = "llm-assistant")]
+            assistant_api_key: self.assistant_api_key.clone(),
+        }
+    }
+    fn persist_settings(&self) {
+        self.persisted().save();
+    }
     fn next(&mut self) {
         self.entry = String::new();
         match self.current {
@@ -101,9 +161,21 @@ impl Default for App {
             langs: ["English".into(), "French".into()],
             state: State::WaitUserAnswer,
             last_score: 0.,
-            dark_theme: true,
+            last_distance: 0,
+            theme_mode: settings::ThemeMode::default(),
+            system_dark: false,
             font_size: Pixels(16.),
             spacing: 5.0,
+            grading_mode: GradingMode::default(),
+            schedule: srs::Deck::default(),
+            #[cfg(feature = "llm-assistant")]
+            assistant_endpoint: String::new(),
+            #[cfg(feature = "llm-assistant")]
+            assistant_api_key: String::new(),
+            #[cfg(feature = "llm-assistant")]
+            topic_input: String::new(),
+            #[cfg(feature = "llm-assistant")]
+            explanation: None,
         }
     }
 }
@@ -113,15 +185,31 @@ enum Message {
     DebugToggle,
     TextInputChanged(String),
     OpenFile,
-    FileOpened(Result<(PathBuf, Arc<([Lang; 2], Vec<Entry>)>), Error>),
+    FileOpened(Result<(DeckSource, Arc<([Lang; 2], Vec<Entry>)>), Error>),
     Correction,
     Next,
     // None,
     Start,
     Enter,
-    ThemeSelected,
+    ThemeModeSelected(settings::ThemeMode),
+    SystemThemeChanged(bool),
     TextFontChanged(f32),
     SpacingChanged(f32),
+    GradingModeToggled,
+    #[cfg(feature = "llm-assistant")]
+    AssistantEndpointChanged(String),
+    #[cfg(feature = "llm-assistant")]
+    AssistantApiKeyChanged(String),
+    #[cfg(feature = "llm-assistant")]
+    TopicInputChanged(String),
+    #[cfg(feature = "llm-assistant")]
+    GenerateDeck(String),
+    #[cfg(feature = "llm-assistant")]
+    DeckGenerated(String, Result<Vec<Entry>, assistant::AssistantError>),
+    #[cfg(feature = "llm-assistant")]
+    ExplainRequested,
+    #[cfg(feature = "llm-assistant")]
+    Explained(Result<String, assistant::AssistantError>),
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -131,6 +219,32 @@ enum Error {
     ParseError,
 }
 
+/// Identifies the deck currently loaded. A deck opened from disk on native targets is a
+/// real file path (also used as the spaced-repetition sidecar's location); on wasm there
+/// is no filesystem, so the browser-picked file name stands in for it, and an
+/// assistant-generated deck is named by its topic.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum DeckSource {
+    Path(PathBuf),
+    Named(String),
+}
+
+impl DeckSource {
+    fn label(&self) -> &str {
+        match self {
+            DeckSource::Path(path) => path.to_str().unwrap_or(""),
+            DeckSource::Named(name) => name,
+        }
+    }
+
+    fn as_path(&self) -> Option<&Path> {
+        match self {
+            DeckSource::Path(path) => Some(path),
+            DeckSource::Named(_) => None,
+        }
+    }
+}
+
 #[derive(Debug)]
 enum State {
     Correcting,
@@ -141,26 +255,47 @@ enum State {
 impl iced::Application for App {
     type Message = Message;
     type Theme = Theme;
+    #[cfg(not(target_family = "wasm"))]
+    type Executor = storage::Executor;
+    #[cfg(target_family = "wasm")]
     type Executor = iced::executor::Default;
     type Flags = ();
 
     fn new(_flag: Self::Flags) -> (Self, Command<Message>) {
-        (Self::default(), Command::none())
+        let persisted = settings::Persisted::load();
+        let mut app = Self::default();
+        app.theme_mode = persisted.theme_mode;
+        app.font_size = Pixels(persisted.font_size);
+        app.spacing = persisted.spacing;
+        app.grading_mode = persisted.grading_mode;
+        #[cfg(feature = "llm-assistant")]
+        {
+            app.assistant_endpoint = persisted.assistant_endpoint;
+            app.assistant_api_key = persisted.assistant_api_key;
+        }
+        #[cfg(not(target_family = "wasm"))]
+        {
+            app.system_dark = matches!(dark_light::detect(), dark_light::Mode::Dark);
+        }
+        (app, Command::none())
     }
 
     fn title(&self) -> String {
         match &self.file {
-            Some(path) => format!("{} — ULang ", path.to_str().unwrap_or("")),
+            Some(source) => format!("{} — ULang ", source.label()),
             None => String::from("ULang"),
         }
     }
 
     fn subscription(&self) -> iced::Subscription<Self::Message> {
-        keyboard::on_key_press(|key, modifiers| match key.as_ref() {
-            Key::Character("o") if modifiers.command() => Some(Message::OpenFile), // Ctrl + o
-            Key::Named(keyboard::key::Named::Enter) => Some(Message::Enter),       // Enter
-            _ => None,
-        })
+        iced::Subscription::batch([
+            keyboard::on_key_press(|key, modifiers| match key.as_ref() {
+                Key::Character("o") if modifiers.command() => Some(Message::OpenFile), // Ctrl + o
+                Key::Named(keyboard::key::Named::Enter) => Some(Message::Enter),       // Enter
+                _ => None,
+            }),
+            system_theme_subscription(),
+        ])
     }
 
     fn update(&mut self, message: Message) -> Command<Message> {
@@ -176,10 +311,14 @@ impl iced::Application for App {
             Message::OpenFile => Command::perform(pick_file(), Message::FileOpened),
             Message::FileOpened(result) => {
                 match result {
-                    Ok((path, content)) => {
+                    Ok((source, content)) => {
                         self.langs = content.0.clone();
+                        self.schedule = match source.as_path() {
+                            Some(path) => srs::Deck::load(path),
+                            None => srs::Deck::default(),
+                        };
                         self.init(content.1.clone());
-                        self.file = Some(path);
+                        self.file = Some(source);
                         self.error = None;
                     }
                     Err(Error::DialogClosed) => (),
@@ -206,23 +345,117 @@ impl iced::Application for App {
             // Message::None => Command::none(),
             Message::Start => {
                 if let Some(_) = self.file {
-                    self.init(self.content.clone());
+                    // Missed entries get re-inserted a few slots ahead within a
+                    // session (see `correct`); drop those extra copies here so a
+                    // fresh round doesn't keep compounding them across restarts.
+                    let mut seen = HashSet::new();
+                    let content: Vec<Entry> = self
+                        .content
+                        .iter()
+                        .cloned()
+                        .filter(|entry| seen.insert(srs::Deck::key(entry)))
+                        .collect();
+                    self.init(content);
                 } else {
                     self.init(App::default().content);
                 }
                 self.state = State::WaitUserAnswer;
                 Command::none()
             }
-            Message::ThemeSelected => {
-                self.dark_theme = !self.dark_theme;
+            Message::ThemeModeSelected(mode) => {
+                self.theme_mode = mode;
+                self.persist_settings();
+                Command::none()
+            }
+            Message::SystemThemeChanged(is_dark) => {
+                self.system_dark = is_dark;
                 Command::none()
             }
             Message::TextFontChanged(new_size) => {
                 self.font_size.0 = new_size;
+                self.persist_settings();
                 Command::none()
             }
             Message::SpacingChanged(new_spacing) => {
                 self.spacing = new_spacing;
+                self.persist_settings();
+                Command::none()
+            }
+            Message::GradingModeToggled => {
+                self.grading_mode = match self.grading_mode {
+                    GradingMode::Strict => GradingMode::Lenient,
+                    GradingMode::Lenient => GradingMode::Strict,
+                };
+                self.persist_settings();
+                Command::none()
+            }
+            #[cfg(feature = "llm-assistant")]
+            Message::AssistantEndpointChanged(endpoint) => {
+                self.assistant_endpoint = endpoint;
+                self.persist_settings();
+                Command::none()
+            }
+            #[cfg(feature = "llm-assistant")]
+            Message::AssistantApiKeyChanged(key) => {
+                self.assistant_api_key = key;
+                self.persist_settings();
+                Command::none()
+            }
+            #[cfg(feature = "llm-assistant")]
+            Message::TopicInputChanged(topic) => {
+                self.topic_input = topic;
+                Command::none()
+            }
+            #[cfg(feature = "llm-assistant")]
+            Message::GenerateDeck(topic) => {
+                let label = topic.clone();
+                Command::perform(
+                    assistant::generate_deck(self.persisted(), topic),
+                    move |result| Message::DeckGenerated(label, result),
+                )
+            }
+            #[cfg(feature = "llm-assistant")]
+            Message::DeckGenerated(topic, Ok(entries)) => {
+                // The assistant doesn't pick a language pair; keep whatever is set.
+                self.schedule = srs::Deck::default();
+                self.init(entries);
+                self.file = Some(DeckSource::Named(topic));
+                self.error = None;
+                Command::none()
+            }
+            #[cfg(feature = "llm-assistant")]
+            Message::DeckGenerated(_, Err(err)) => {
+                self.explanation = Some(match err {
+                    assistant::AssistantError::MissingConfig => {
+                        "Set an assistant endpoint and API key in Settings first.".to_string()
+                    }
+                    assistant::AssistantError::Http => "Couldn't reach the assistant.".to_string(),
+                    assistant::AssistantError::InvalidReply => {
+                        "The assistant's reply couldn't be parsed into a deck.".to_string()
+                    }
+                });
+                Command::none()
+            }
+            #[cfg(feature = "llm-assistant")]
+            Message::ExplainRequested => match self.current {
+                Some(idx) => {
+                    let prompt = self.content[idx].get(1).to_string();
+                    let expected = self.content[idx].get(0).to_string();
+                    Command::perform(
+                        assistant::explain(self.persisted(), prompt, expected),
+                        Message::Explained,
+                    )
+                }
+                None => Command::none(),
+            },
+            #[cfg(feature = "llm-assistant")]
+            Message::Explained(Ok(explanation)) => {
+                self.explanation = Some(explanation);
+                Command::none()
+            }
+            #[cfg(feature = "llm-assistant")]
+            Message::Explained(Err(_)) => {
+                self.explanation = Some("Couldn't reach the assistant.".to_string());
                 Command::none()
             }
         }
@@ -269,6 +502,14 @@ impl iced::Application for App {
                 .spacing(5.0)
         };
 
+        #[cfg(feature = "llm-assistant")]
+        let error_log = text(match (&self.error, &self.explanation) {
+            (Some(err), _) => format!("{:?}: invalid file", err),
+            (None, Some(explanation)) => explanation.clone(),
+            (None, None) => String::new(),
+        })
+        .size(self.font_size);
+        #[cfg(not(feature = "llm-assistant"))]
         let error_log = text(match &self.error {
             Some(err) => format!("{:?}: invalid file", err),
             None => "".to_string(),
@@ -315,6 +556,19 @@ impl iced::Application for App {
                 if let Some(nb) = &self.current {
                     variable = variable.push(text(&self.content[*nb].get(0)).size(self.font_size))
                 }
+                if self.last_distance > 0 {
+                    variable = variable.push(Space::new(10, 0));
+                    variable = variable
+                        .push(text(format!("(Δ {})", self.last_distance)).size(self.font_size));
+                }
+                #[cfg(feature = "llm-assistant")]
+                {
+                    variable = variable.push(Space::new(10, 0));
+                    variable = variable.push(
+                        button(text("Why?").size(self.font_size))
+                            .on_press(Message::ExplainRequested),
+                    );
+                }
             }
             _ => (),
         }
@@ -372,7 +626,12 @@ impl iced::Application for App {
     }
 
     fn theme(&self) -> Theme {
-        if self.dark_theme {
+        let dark = match self.theme_mode {
+            settings::ThemeMode::Dark => true,
+            settings::ThemeMode::Light => false,
+            settings::ThemeMode::Auto => self.system_dark,
+        };
+        if dark {
             Theme::Dark
         } else {
             Theme::Light
@@ -380,8 +639,24 @@ impl iced::Application for App {
     }
 }
 
+/// Polls the OS appearance so `ThemeMode::Auto` can react to live changes.
+/// Not available on wasm, where there is no portable way to poll it outside the DOM.
+#[cfg(not(target_family = "wasm"))]
+fn system_theme_subscription() -> iced::Subscription<Message> {
+    iced::subscription::unfold("system-theme", (), |_| async move {
+        storage::sleep(std::time::Duration::from_secs(2)).await;
+        let is_dark = matches!(dark_light::detect(), dark_light::Mode::Dark);
+        (Message::SystemThemeChanged(is_dark), ())
+    })
+}
+
+#[cfg(target_family = "wasm")]
+fn system_theme_subscription() -> iced::Subscription<Message> {
+    iced::Subscription::none()
+}
+
 #[cfg(not(target_family = "wasm"))]
-async fn pick_file() -> Result<(PathBuf, Arc<([Lang; 2], Vec<Entry>)>), Error> {
+async fn pick_file() -> Result<(DeckSource, Arc<([Lang; 2], Vec<Entry>)>), Error> {
     let opt_handle = rfd::AsyncFileDialog::new()
         .set_title("Choose a json file...")
         .add_filter("Json (*.json)", &["json"])
@@ -391,9 +666,9 @@ async fn pick_file() -> Result<(PathBuf, Arc<([Lang; 2], Vec<Entry>)>), Error> {
     match opt_handle {
         Some(handle) => {
             let path = handle.path();
-            match async_std::fs::read_to_string(path).await {
+            match storage::read_deck(path).await {
                 Ok(raw) => match parse(&raw) {
-                    Ok(data) => Ok((path.into(), Arc::new(data))),
+                    Ok(data) => Ok((DeckSource::Path(path.into()), Arc::new(data))),
                     Err(_) => Err(Error::ParseError),
                 },
                 Err(_) => Err(Error::IoError),
@@ -402,3 +677,27 @@ async fn pick_file() -> Result<(PathBuf, Arc<([Lang; 2], Vec<Entry>)>), Error> {
         None => Err(Error::DialogClosed),
     }
 }
+
+#[cfg(target_family = "wasm")]
+async fn pick_file() -> Result<(DeckSource, Arc<([Lang; 2], Vec<Entry>)>), Error> {
+    let opt_handle = rfd::AsyncFileDialog::new()
+        .set_title("Choose a json file...")
+        .add_filter("Json (*.json)", &["json"])
+        .add_filter("All files (*.*)", &["*"])
+        .pick_file()
+        .await;
+    match opt_handle {
+        Some(handle) => {
+            let name = handle.file_name();
+            let bytes = handle.read().await;
+            match std::str::from_utf8(&bytes) {
+                Ok(raw) => match parse(raw) {
+                    Ok(data) => Ok((DeckSource::Named(name), Arc::new(data))),
+                    Err(_) => Err(Error::ParseError),
+                },
+                Err(_) => Err(Error::ParseError),
+            }
+        }
+        None => Err(Error::DialogClosed),
+    }
+}
@@ -0,0 +1,188 @@
+use std::fs;
+use std::path::PathBuf;
+
+use iced::widget::{column, container, radio, row, slider, text};
+use iced_aw::menu;
+use serde::{Deserialize, Serialize};
+
+use crate::{App, Message};
+
+/// How strictly a typed answer is compared against the expected one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GradingMode {
+    /// Only a match after trimming, lowercasing and stripping diacritics earns credit.
+    Strict,
+    /// Partial credit is awarded based on normalized edit distance.
+    Lenient,
+}
+
+impl Default for GradingMode {
+    fn default() -> Self {
+        GradingMode::Strict
+    }
+}
+
+/// Which theme to render: a fixed choice, or follow the OS appearance.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ThemeMode {
+    Light,
+    Dark,
+    Auto,
+}
+
+impl Default for ThemeMode {
+    fn default() -> Self {
+        ThemeMode::Dark
+    }
+}
+
+/// The subset of `App` state that survives between runs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct Persisted {
+    pub(crate) theme_mode: ThemeMode,
+    pub(crate) font_size: f32,
+    pub(crate) spacing: f32,
+    pub(crate) grading_mode: GradingMode,
+    #[cfg(feature = "llm-assistant")]
+    #[serde(default)]
+    pub(crate) assistant_endpoint: String,
+    #[cfg(feature = "llm-assistant")]
+    #[serde(default)]
+    pub(crate) assistant_api_key: String,
+}
+
+impl Default for Persisted {
+    fn default() -> Self {
+        Self {
+            theme_mode: ThemeMode::default(),
+            font_size: 16.0,
+            spacing: 5.0,
+            grading_mode: GradingMode::default(),
+            #[cfg(feature = "llm-assistant")]
+            assistant_endpoint: String::new(),
+            #[cfg(feature = "llm-assistant")]
+            assistant_api_key: String::new(),
+        }
+    }
+}
+
+#[cfg(not(target_family = "wasm"))]
+fn config_path() -> Option<PathBuf> {
+    directories::ProjectDirs::from("", "", "ulang")
+        .map(|dirs| dirs.config_dir().join("settings.json"))
+}
+
+/// `directories`/`dirs-sys` don't target `wasm32-unknown-unknown`; settings simply
+/// don't persist across reloads in the browser build.
+#[cfg(target_family = "wasm")]
+fn config_path() -> Option<PathBuf> {
+    None
+}
+
+impl Persisted {
+    pub(crate) fn load() -> Self {
+        config_path()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|raw| serde_json::from_str(&raw).ok())
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn save(&self) {
+        let Some(path) = config_path() else {
+            return;
+        };
+        if let Some(parent) = path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        if let Ok(raw) = serde_json::to_string_pretty(self) {
+            let _ = fs::write(path, raw);
+        }
+    }
+}
+
+impl App {
+    pub(crate) fn view_settings(&self) -> menu::Menu<'_, Message, iced::Theme, iced::Renderer> {
+        let theme_mode = column![
+            text("Theme").size(self.font_size),
+            row![
+                radio(
+                    "Light",
+                    ThemeMode::Light,
+                    Some(self.theme_mode),
+                    Message::ThemeModeSelected
+                ),
+                radio(
+                    "Dark",
+                    ThemeMode::Dark,
+                    Some(self.theme_mode),
+                    Message::ThemeModeSelected
+                ),
+                radio(
+                    "Auto",
+                    ThemeMode::Auto,
+                    Some(self.theme_mode),
+                    Message::ThemeModeSelected
+                ),
+            ]
+            .spacing(self.spacing),
+        ]
+        .spacing(self.spacing / 2.0);
+
+        let font_size = row![
+            text("Font size").size(self.font_size),
+            slider(10.0..=32.0, self.font_size.0, Message::TextFontChanged),
+        ]
+        .spacing(self.spacing);
+
+        let spacing = row![
+            text("Spacing").size(self.font_size),
+            slider(0.0..=20.0, self.spacing, Message::SpacingChanged),
+        ]
+        .spacing(self.spacing);
+
+        let lenient_grading = iced::widget::checkbox(
+            "Lenient grading (partial credit)",
+            self.grading_mode == GradingMode::Lenient,
+        )
+        .on_toggle(|_| Message::GradingModeToggled);
+
+        #[cfg(feature = "llm-assistant")]
+        let assistant = column![
+            text("LLM assistant").size(self.font_size),
+            iced::widget::text_input("Endpoint URL", &self.assistant_endpoint)
+                .size(self.font_size)
+                .on_input(Message::AssistantEndpointChanged),
+            iced::widget::text_input("API key", &self.assistant_api_key)
+                .size(self.font_size)
+                .password()
+                .on_input(Message::AssistantApiKeyChanged),
+            row![
+                iced::widget::text_input("Deck topic", &self.topic_input)
+                    .size(self.font_size)
+                    .on_input(Message::TopicInputChanged),
+                iced::widget::button(text("Generate").size(self.font_size))
+                    .on_press(Message::GenerateDeck(self.topic_input.clone())),
+            ]
+            .spacing(self.spacing),
+        ]
+        .spacing(self.spacing / 2.0);
+
+        #[cfg(feature = "llm-assistant")]
+        let content = container(
+            column![theme_mode, font_size, spacing, lenient_grading, assistant]
+                .spacing(self.spacing)
+                .padding(self.spacing),
+        );
+        #[cfg(not(feature = "llm-assistant"))]
+        let content = container(
+            column![theme_mode, font_size, spacing, lenient_grading]
+                .spacing(self.spacing)
+                .padding(self.spacing),
+        );
+
+        menu::Menu::new(iced_aw::menu_items!((content)))
+            .max_width(220.0)
+            .offset(5.0)
+            .spacing(5.0)
+    }
+}
@@ -0,0 +1,157 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use chrono::{Duration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+use grammar::Entry;
+
+/// Per-entry spaced-repetition state, scored with the SuperMemo-2 algorithm.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct Schedule {
+    pub(crate) n: u32,
+    pub(crate) ease: f32,
+    pub(crate) interval: u32,
+    pub(crate) due_date: NaiveDate,
+}
+
+impl Default for Schedule {
+    fn default() -> Self {
+        Self {
+            n: 0,
+            ease: 2.5,
+            interval: 0,
+            due_date: Utc::now().date_naive(),
+        }
+    }
+}
+
+impl Schedule {
+    /// Map a 0..=1 grading score onto the SM-2 quality scale (0..=5).
+    pub(crate) fn quality(score: f32) -> u8 {
+        (score.clamp(0.0, 1.0) * 5.0).round() as u8
+    }
+
+    /// Apply one SM-2 review step for quality grade `q`, due from `today`.
+    pub(crate) fn review(&mut self, q: u8, today: NaiveDate) {
+        if q < 3 {
+            self.n = 0;
+            self.interval = 1;
+        } else {
+            self.n += 1;
+            self.interval = match self.n {
+                1 => 1,
+                2 => 6,
+                _ => (self.interval as f32 * self.ease).round() as u32,
+            };
+        }
+        let q = q as f32;
+        self.ease = (self.ease + 0.1 - (5.0 - q) * (0.08 + (5.0 - q) * 0.02)).max(1.3);
+        self.due_date = today + Duration::days(self.interval as i64);
+    }
+}
+
+/// Review schedules for a whole deck, keyed by entry content so they survive reshuffles,
+/// persisted to a sidecar JSON file next to the loaded deck.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub(crate) struct Deck {
+    schedules: HashMap<String, Schedule>,
+}
+
+impl Deck {
+    /// Stable key for an entry: unaffected by shuffling or session reordering.
+    pub(crate) fn key(entry: &Entry) -> String {
+        format!("{}\u{1f}{}", entry.get(0), entry.get(1))
+    }
+
+    pub(crate) fn entry(&mut self, key: &str) -> &mut Schedule {
+        self.schedules.entry(key.to_string()).or_default()
+    }
+
+    pub(crate) fn due_date(&self, key: &str) -> NaiveDate {
+        self.schedules
+            .get(key)
+            .map(|s| s.due_date)
+            .unwrap_or_else(|| Utc::now().date_naive())
+    }
+
+    fn sidecar_path(deck_path: &Path) -> PathBuf {
+        deck_path.with_extension("srs.json")
+    }
+
+    pub(crate) fn load(deck_path: &Path) -> Self {
+        std::fs::read_to_string(Self::sidecar_path(deck_path))
+            .ok()
+            .and_then(|raw| serde_json::from_str(&raw).ok())
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn save(&self, deck_path: &Path) {
+        if let Ok(raw) = serde_json::to_string_pretty(self) {
+            let _ = std::fs::write(Self::sidecar_path(deck_path), raw);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use grammar::GramClass;
+
+    #[test]
+    fn quality_maps_score_onto_zero_to_five() {
+        assert_eq!(Schedule::quality(0.0), 0);
+        assert_eq!(Schedule::quality(0.6), 3);
+        assert_eq!(Schedule::quality(1.0), 5);
+    }
+
+    #[test]
+    fn review_resets_interval_and_repetitions_below_quality_three() {
+        let today = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+        let mut schedule = Schedule {
+            n: 4,
+            ease: 2.5,
+            interval: 30,
+            due_date: today,
+        };
+        schedule.review(2, today);
+        assert_eq!(schedule.n, 0);
+        assert_eq!(schedule.interval, 1);
+        assert_eq!(schedule.due_date, today + Duration::days(1));
+    }
+
+    #[test]
+    fn review_grows_interval_across_the_first_three_repetitions() {
+        let today = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+        let mut schedule = Schedule::default();
+
+        schedule.review(5, today);
+        assert_eq!(schedule.n, 1);
+        assert_eq!(schedule.interval, 1);
+
+        schedule.review(5, today);
+        assert_eq!(schedule.n, 2);
+        assert_eq!(schedule.interval, 6);
+
+        let ease_before_third = schedule.ease;
+        schedule.review(5, today);
+        assert_eq!(schedule.n, 3);
+        assert_eq!(schedule.interval, (6.0 * ease_before_third).round() as u32);
+    }
+
+    #[test]
+    fn review_never_drops_ease_below_the_sm2_floor() {
+        let today = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+        let mut schedule = Schedule::default();
+        for _ in 0..20 {
+            schedule.review(0, today);
+        }
+        assert!(schedule.ease >= 1.3);
+    }
+
+    #[test]
+    fn deck_key_is_stable_for_the_same_entry_content() {
+        let entry = Entry("yes".into(), "oui".into(), GramClass::Adverb);
+        assert_eq!(Deck::key(&entry), Deck::key(&entry));
+    }
+}
@@ -0,0 +1,95 @@
+//! Deck file I/O, behind a backend chosen at compile time via Cargo features
+//! (`backend-async-std` by default, `backend-tokio`, `backend-smol`). Extracting this
+//! the way `requestty` extracts `crossterm` behind its own feature-selected backend
+//! lets embedders that already drive a tokio or smol reactor reuse it instead of
+//! pulling in a second one just for `pick_file`.
+
+use std::path::Path;
+use std::time::Duration;
+
+use crate::Error;
+
+#[cfg(not(any(
+    feature = "backend-async-std",
+    feature = "backend-tokio",
+    feature = "backend-smol"
+)))]
+compile_error!(
+    "enable exactly one of the `backend-async-std`, `backend-tokio`, or `backend-smol` features"
+);
+
+#[cfg(any(
+    all(feature = "backend-async-std", feature = "backend-tokio"),
+    all(feature = "backend-async-std", feature = "backend-smol"),
+    all(feature = "backend-tokio", feature = "backend-smol"),
+))]
+compile_error!(
+    "only one of the `backend-async-std`, `backend-tokio`, or `backend-smol` features may be enabled at a time"
+);
+
+/// An async I/O backend for reading deck files, plus the iced executor that matches
+/// its reactor. Selected at compile time so embedders that already run a tokio or
+/// smol reactor can plug it in instead of pulling in a second one.
+pub(crate) trait Backend {
+    type Executor: iced::Executor;
+
+    async fn read_deck(path: &Path) -> Result<String, Error>;
+    async fn sleep(duration: Duration);
+}
+
+pub(crate) struct SelectedBackend;
+
+#[cfg(feature = "backend-async-std")]
+impl Backend for SelectedBackend {
+    type Executor = iced_futures::backend::native::async_std::Executor;
+
+    async fn read_deck(path: &Path) -> Result<String, Error> {
+        async_std::fs::read_to_string(path)
+            .await
+            .map_err(|_| Error::IoError)
+    }
+
+    async fn sleep(duration: Duration) {
+        async_std::task::sleep(duration).await;
+    }
+}
+
+#[cfg(feature = "backend-tokio")]
+impl Backend for SelectedBackend {
+    type Executor = iced_futures::backend::native::tokio::Executor;
+
+    async fn read_deck(path: &Path) -> Result<String, Error> {
+        tokio::fs::read_to_string(path)
+            .await
+            .map_err(|_| Error::IoError)
+    }
+
+    async fn sleep(duration: Duration) {
+        tokio::time::sleep(duration).await;
+    }
+}
+
+#[cfg(feature = "backend-smol")]
+impl Backend for SelectedBackend {
+    type Executor = iced_futures::backend::native::smol::Executor;
+
+    async fn read_deck(path: &Path) -> Result<String, Error> {
+        smol::fs::read_to_string(path)
+            .await
+            .map_err(|_| Error::IoError)
+    }
+
+    async fn sleep(duration: Duration) {
+        smol::Timer::after(duration).await;
+    }
+}
+
+pub(crate) type Executor = <SelectedBackend as Backend>::Executor;
+
+pub(crate) async fn read_deck(path: &Path) -> Result<String, Error> {
+    SelectedBackend::read_deck(path).await
+}
+
+pub(crate) async fn sleep(duration: Duration) {
+    SelectedBackend::sleep(duration).await;
+}
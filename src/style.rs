@@ -0,0 +1,39 @@
+use iced::widget::button;
+use iced::{Background, Theme};
+
+/// Button style used for the top-level header buttons (`File`, `Settings`, ...).
+#[derive(Debug, Clone, Copy)]
+pub struct Header {
+    dark: bool,
+}
+
+impl From<&Theme> for Header {
+    fn from(theme: &Theme) -> Self {
+        Self {
+            dark: matches!(theme, Theme::Dark),
+        }
+    }
+}
+
+impl button::StyleSheet for Header {
+    type Style = Theme;
+
+    fn active(&self, style: &Self::Style) -> button::Appearance {
+        let palette = style.extended_palette();
+        button::Appearance {
+            background: Some(Background::Color(palette.background.base.color)),
+            text_color: palette.background.base.text,
+            border: iced::Border::default(),
+            ..Default::default()
+        }
+    }
+
+    fn hovered(&self, style: &Self::Style) -> button::Appearance {
+        let palette = style.extended_palette();
+        button::Appearance {
+            background: Some(Background::Color(palette.background.weak.color)),
+            text_color: palette.background.weak.text,
+            ..self.active(style)
+        }
+    }
+}